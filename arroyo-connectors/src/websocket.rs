@@ -1,7 +1,7 @@
 use std::convert::Infallible;
 use std::time::Duration;
 
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use arroyo_rpc::OperatorConfig;
 use axum::response::sse::Event;
 use futures::{SinkExt, StreamExt};
@@ -94,45 +94,333 @@ impl Connector for WebsocketConnector {
 
             let (mut tx, mut rx) = ws_stream.split();
 
-            if let Some(msg) = table.subscription_message {
-                match tx
-                    .send(tungstenite::Message::Text(msg.clone().into()))
-                    .await
-                {
-                    Ok(_) => {
-                        send(false, false, "Sent subscription message".to_string()).await;
+            // Use `idle_timeout` as the read deadline so a silent or half-open
+            // server is reported instead of hanging on the default 30s.
+            let idle_timeout = table
+                .idle_timeout
+                .map(Duration::from_millis)
+                .unwrap_or_else(|| Duration::from_secs(30));
+
+            match table.protocol {
+                Protocol::GraphqlWs => {
+                    let query = match &table.query {
+                        Some(q) => q.clone(),
+                        None => {
+                            send(
+                                true,
+                                true,
+                                "'query' must be set for the graphql_ws protocol".to_string(),
+                            )
+                            .await;
+                            return;
+                        }
+                    };
+
+                    if let Err(e) = tx
+                        .send(tungstenite::Message::Text(
+                            r#"{"type":"connection_init"}"#.to_string(),
+                        ))
+                        .await
+                    {
+                        send(true, true, format!("Failed to send connection_init: {:?}", e)).await;
+                        return;
                     }
-                    Err(e) => {
-                        send(
-                            true,
-                            true,
-                            format!("Failed to send subscription message: {:?}", e),
-                        )
-                        .await;
+
+                    // Wait for the server to acknowledge the connection, skipping any
+                    // keep-alives it sends in the meantime.
+                    loop {
+                        match tokio::time::timeout(idle_timeout, rx.next()).await {
+                            Ok(Some(Ok(tungstenite::Message::Text(text)))) => {
+                                let value: serde_json::Value = match serde_json::from_str(&text) {
+                                    Ok(value) => value,
+                                    Err(_) => continue,
+                                };
+                                match value.get("type").and_then(|t| t.as_str()) {
+                                    Some("connection_ack") => {
+                                        send(false, false, "Received connection_ack".to_string())
+                                            .await;
+                                        break;
+                                    }
+                                    Some("ka") | Some("ping") => continue,
+                                    Some("connection_error") | Some("error") => {
+                                        send(
+                                            true,
+                                            true,
+                                            format!("Received error during connection_init: {}", text),
+                                        )
+                                        .await;
+                                        return;
+                                    }
+                                    _ => continue,
+                                }
+                            }
+                            Ok(Some(Ok(tungstenite::Message::Ping(data)))) => {
+                                let _ = tx.send(tungstenite::Message::Pong(data)).await;
+                                continue;
+                            }
+                            Ok(Some(Ok(_))) => continue,
+                            Ok(Some(Err(e))) => {
+                                send(true, true, format!("Received error from websocket: {:?}", e))
+                                    .await;
+                                return;
+                            }
+                            Ok(None) => {
+                                send(
+                                    true,
+                                    true,
+                                    "Websocket disconnected before connection_ack".to_string(),
+                                )
+                                .await;
+                                return;
+                            }
+                            Err(_) => {
+                                send(
+                                    true,
+                                    true,
+                                    format!(
+                                        "Did not receive connection_ack after {}ms",
+                                        idle_timeout.as_millis()
+                                    ),
+                                )
+                                .await;
+                                return;
+                            }
+                        }
+                    }
+
+                    let start = serde_json::json!({
+                        "type": "start",
+                        "id": "1",
+                        "payload": { "query": query },
+                    });
+                    if let Err(e) = tx
+                        .send(tungstenite::Message::Text(start.to_string()))
+                        .await
+                    {
+                        send(true, true, format!("Failed to send start message: {:?}", e)).await;
                         return;
                     }
+                    send(false, false, "Sent subscription start message".to_string()).await;
+
+                    // Validate that the subscription yields at least one data frame.
+                    loop {
+                        match tokio::time::timeout(idle_timeout, rx.next()).await {
+                            Ok(Some(Ok(tungstenite::Message::Text(text)))) => {
+                                let value: serde_json::Value = match serde_json::from_str(&text) {
+                                    Ok(value) => value,
+                                    Err(_) => continue,
+                                };
+                                match value.get("type").and_then(|t| t.as_str()) {
+                                    Some("data") => {
+                                        send(
+                                            false,
+                                            false,
+                                            "Received data frame from subscription".to_string(),
+                                        )
+                                        .await;
+                                        break;
+                                    }
+                                    Some("ka") | Some("ping") => continue,
+                                    Some("error") => {
+                                        send(
+                                            true,
+                                            true,
+                                            format!("Subscription returned error: {}", text),
+                                        )
+                                        .await;
+                                        return;
+                                    }
+                                    Some("complete") => {
+                                        send(
+                                            true,
+                                            true,
+                                            "Subscription completed before delivering data"
+                                                .to_string(),
+                                        )
+                                        .await;
+                                        return;
+                                    }
+                                    _ => continue,
+                                }
+                            }
+                            Ok(Some(Ok(tungstenite::Message::Ping(data)))) => {
+                                let _ = tx.send(tungstenite::Message::Pong(data)).await;
+                                continue;
+                            }
+                            Ok(Some(Ok(_))) => continue,
+                            Ok(Some(Err(e))) => {
+                                send(true, true, format!("Received error from websocket: {:?}", e))
+                                    .await;
+                                return;
+                            }
+                            Ok(None) => {
+                                send(
+                                    true,
+                                    true,
+                                    "Websocket disconnected before delivering data".to_string(),
+                                )
+                                .await;
+                                return;
+                            }
+                            Err(_) => {
+                                send(
+                                    true,
+                                    true,
+                                    format!(
+                                        "Did not receive any data after {}ms",
+                                        idle_timeout.as_millis()
+                                    ),
+                                )
+                                .await;
+                                return;
+                            }
+                        }
+                    }
                 }
-            }
+                Protocol::Raw => {
+                    // Build the ordered handshake: the explicit step list when set,
+                    // otherwise the single subscription_message for back-compat.
+                    let steps: Vec<SubscriptionStep> = match &table.subscription_messages {
+                        Some(steps) if !steps.is_empty() => steps.clone(),
+                        _ => table
+                            .subscription_message
+                            .as_ref()
+                            .map(|m| SubscriptionStep {
+                                message: m.0.clone(),
+                                delay_ms: None,
+                                await_response: false,
+                                expect: None,
+                            })
+                            .into_iter()
+                            .collect(),
+                    };
 
-            tokio::select! {
-                message = rx.next() => {
-                    match message {
-                        Some(Ok(_)) => {
-                            send(false, false, "Received message from websocket".to_string()).await;
-                        },
-                        Some(Err(e)) => {
-                            send(true, true, format!("Received error from websocket: {:?}", e)).await;
-                            return;
+                    for (i, step) in steps.iter().enumerate() {
+                        if let Some(delay) = step.delay_ms {
+                            tokio::time::sleep(Duration::from_millis(delay)).await;
                         }
-                        None => {
-                            send(true, true, "Websocket disconnected before sending message".to_string()).await;
+
+                        if let Err(e) = tx
+                            .send(tungstenite::Message::Text(step.message.clone()))
+                            .await
+                        {
+                            send(
+                                true,
+                                true,
+                                format!("Failed to send subscription message {}: {:?}", i + 1, e),
+                            )
+                            .await;
                             return;
                         }
+                        send(
+                            false,
+                            false,
+                            format!("Sent subscription message {}/{}", i + 1, steps.len()),
+                        )
+                        .await;
+
+                        if !step.await_response {
+                            continue;
+                        }
+
+                        // Read and validate one reply before moving to the next step,
+                        // answering keep-alive Pings without consuming the step.
+                        loop {
+                            match tokio::time::timeout(idle_timeout, rx.next()).await {
+                                Ok(Some(Ok(tungstenite::Message::Ping(data)))) => {
+                                    let _ = tx.send(tungstenite::Message::Pong(data)).await;
+                                    continue;
+                                }
+                                Ok(Some(Ok(msg))) => {
+                                    if let Some(expected) = &step.expect {
+                                        let text = msg.into_text().unwrap_or_default();
+                                        if !text.contains(expected) {
+                                            send(
+                                                true,
+                                                true,
+                                                format!(
+                                                    "Response to message {} did not contain '{}'",
+                                                    i + 1,
+                                                    expected
+                                                ),
+                                            )
+                                            .await;
+                                            return;
+                                        }
+                                    }
+                                    send(
+                                        false,
+                                        false,
+                                        format!("Received expected response to message {}", i + 1),
+                                    )
+                                    .await;
+                                    break;
+                                }
+                                Ok(Some(Err(e))) => {
+                                    send(true, true, format!("Received error from websocket: {:?}", e))
+                                        .await;
+                                    return;
+                                }
+                                Ok(None) => {
+                                    send(
+                                        true,
+                                        true,
+                                        format!("Websocket disconnected while awaiting response to message {}", i + 1),
+                                    )
+                                    .await;
+                                    return;
+                                }
+                                Err(_) => {
+                                    send(
+                                        true,
+                                        true,
+                                        format!(
+                                            "No response to message {} after {}ms",
+                                            i + 1,
+                                            idle_timeout.as_millis()
+                                        ),
+                                    )
+                                    .await;
+                                    return;
+                                }
+                            }
+                        }
+                    }
+
+                    // Wait for a first frame, auto-replying to keep-alive Pings
+                    // and treating a silent connection as an idle timeout.
+                    loop {
+                        match tokio::time::timeout(idle_timeout, rx.next()).await {
+                            Ok(Some(Ok(tungstenite::Message::Ping(data)))) => {
+                                let _ = tx.send(tungstenite::Message::Pong(data)).await;
+                                continue;
+                            }
+                            Ok(Some(Ok(_))) => {
+                                send(false, false, "Received message from websocket".to_string()).await;
+                                break;
+                            }
+                            Ok(Some(Err(e))) => {
+                                send(true, true, format!("Received error from websocket: {:?}", e)).await;
+                                return;
+                            }
+                            Ok(None) => {
+                                send(true, true, "Websocket disconnected before sending message".to_string()).await;
+                                return;
+                            }
+                            Err(_) => {
+                                send(
+                                    true,
+                                    true,
+                                    format!(
+                                        "Did not receive any messages after {}ms",
+                                        idle_timeout.as_millis()
+                                    ),
+                                )
+                                .await;
+                                return;
+                            }
+                        }
                     }
-                }
-                _ = tokio::time::sleep(Duration::from_secs(30)) => {
-                    send(true, true, "Did not receive any messages after 30 seconds".to_string()).await;
-                    return;
                 }
             }
 
@@ -196,6 +484,19 @@ impl Connector for WebsocketConnector {
         let endpoint = pull_opt("endpoint", opts)?;
         let subscription_message = opts.remove("subscription_message");
 
+        let idle_timeout = opts
+            .remove("idle_timeout")
+            .map(|s| s.parse())
+            .transpose()
+            .map_err(|_| anyhow!("'idle_timeout' must be a non-negative integer"))?;
+
+        let protocol = match opts.remove("protocol").as_deref() {
+            None | Some("raw") => Protocol::Raw,
+            Some("graphql_ws") => Protocol::GraphqlWs,
+            Some(other) => bail!("unknown protocol '{}'; expected 'raw' or 'graphql_ws'", other),
+        };
+        let query = opts.remove("query");
+
         self.from_config(
             None,
             name,
@@ -203,6 +504,10 @@ impl Connector for WebsocketConnector {
             WebsocketTable {
                 endpoint,
                 subscription_message: subscription_message.map(SubscriptionMessage),
+                subscription_messages: None,
+                idle_timeout,
+                protocol,
+                query,
             },
             schema,
         )